@@ -0,0 +1,26 @@
+/// Signature algorithms supported by [`crate::Signatory`] and [`crate::Verifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// `MD5(<payload>&key=<secret>)`, uppercase hex on the JSON param path
+    /// (base64 in the HTTP `Signature` header). The original, weak default.
+    Md5,
+    /// `HMAC-SHA256(secret, payload)`, uppercase hex on the JSON param path
+    /// (base64 in the HTTP `Signature` header). The secret is the MAC key,
+    /// not a concatenated field.
+    HmacSha256,
+    /// RSA-PKCS1v1.5 signature over the SHA-256 digest of the payload, base64-encoded.
+    /// `key` holds a PEM-encoded RSA private key when signing and a PEM-encoded
+    /// RSA public key when verifying.
+    RsaSha256,
+}
+
+impl Algorithm {
+    /// The lowercase name used in the HTTP `Signature` header's `algorithm` parameter.
+    pub(crate) fn http_signature_name(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "md5",
+            Algorithm::HmacSha256 => "hmac-sha256",
+            Algorithm::RsaSha256 => "rsa-sha256",
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+
+/// Deterministically serializes `params` to a canonical JSON string: object
+/// keys are sorted lexicographically at every depth, there is no insignificant
+/// whitespace, and integers are emitted without decimal points.
+///
+/// Two semantically identical payloads always canonicalize to the same
+/// string, so it is safe to feed directly into a signature digest.
+pub fn canonicalize(params: &HashMap<String, Value>) -> String {
+    let mut entries: Vec<(&String, &Value)> = params.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let body: String = entries
+        .iter()
+        .map(|(key, value)| format!("{}:{}", canonicalize_string(key), canonicalize_value(value)))
+        .collect::<Vec<String>>()
+        .join(",");
+
+    format!("{{{}}}", body)
+}
+
+fn canonicalize_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => canonicalize_number(n),
+        Value::String(s) => canonicalize_string(s),
+        Value::Array(items) => {
+            let body: String = items
+                .iter()
+                .map(canonicalize_value)
+                .collect::<Vec<String>>()
+                .join(",");
+            format!("[{}]", body)
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let body: String = entries
+                .iter()
+                .map(|(key, value)| {
+                    format!("{}:{}", canonicalize_string(key), canonicalize_value(value))
+                })
+                .collect::<Vec<String>>()
+                .join(",");
+            format!("{{{}}}", body)
+        }
+    }
+}
+
+/// Renders integer numbers (those held as `i64`/`u64`) without a decimal point,
+/// matching the canonical-JSON convention used by federated/Matrix-style
+/// signing. Floating-point numbers keep `serde_json`'s own formatting, so a
+/// float such as `1.0` is rendered as `1.0`, not `1`.
+fn canonicalize_number(n: &Number) -> String {
+    if let Some(i) = n.as_i64() {
+        i.to_string()
+    } else if let Some(u) = n.as_u64() {
+        u.to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+fn canonicalize_string(s: &str) -> String {
+    // `serde_json` already escapes consistently (control characters, quotes,
+    // backslashes); reuse it rather than hand-rolling escaping rules.
+    serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonicalize_sorts_keys_and_preserves_types() {
+        let mut params = HashMap::new();
+        params.insert("b".to_string(), json!(1));
+        params.insert("a".to_string(), json!({"y": true, "x": [1, 2, 3]}));
+        params.insert("c".to_string(), json!("hello"));
+
+        let canonical = canonicalize(&params);
+
+        assert_eq!(
+            canonical,
+            r#"{"a":{"x":[1,2,3],"y":true},"b":1,"c":"hello"}"#
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// Errors returned by [`crate::Signatory`]'s public methods, so malformed or
+/// attacker-controlled input (bad base64, invalid UTF-8, broken JSON) returns
+/// a `Result` instead of panicking the whole process.
+#[derive(Debug)]
+pub enum SignatoryError {
+    /// `params` was empty.
+    EmptyParams,
+    /// Base64 decoding failed.
+    Base64(base64::DecodeError),
+    /// Decoded bytes were not valid UTF-8.
+    Utf8(std::string::FromUtf8Error),
+    /// JSON (de)serialization failed.
+    Json(serde_json::Error),
+    /// Signing itself failed (e.g. an invalid HMAC key or malformed PEM).
+    Sign(String),
+    /// An HTTP request was missing a header required to build/verify the
+    /// signing string (e.g. `date` or `digest`).
+    MissingHeader(&'static str),
+    /// The `Signature` header could not be parsed into its `key=value` fields.
+    MalformedSignatureHeader,
+    /// The request's `digest` header did not match the SHA-256 digest of its body.
+    DigestMismatch,
+    /// The regenerated (or, for RSA, re-verified) signature did not match the
+    /// one supplied with the request.
+    SignatureMismatch,
+}
+
+impl fmt::Display for SignatoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatoryError::EmptyParams => write!(f, "params is empty"),
+            SignatoryError::Base64(e) => write!(f, "base64 decode error: {}", e),
+            SignatoryError::Utf8(e) => write!(f, "invalid UTF-8: {}", e),
+            SignatoryError::Json(e) => write!(f, "JSON error: {}", e),
+            SignatoryError::Sign(msg) => write!(f, "signing failed: {}", msg),
+            SignatoryError::MissingHeader(name) => write!(f, "request is missing the `{}` header", name),
+            SignatoryError::MalformedSignatureHeader => {
+                write!(f, "Signature header is malformed")
+            }
+            SignatoryError::DigestMismatch => {
+                write!(f, "digest header does not match request body")
+            }
+            SignatoryError::SignatureMismatch => write!(f, "signature does not match"),
+        }
+    }
+}
+
+impl std::error::Error for SignatoryError {}
+
+impl From<base64::DecodeError> for SignatoryError {
+    fn from(e: base64::DecodeError) -> Self {
+        SignatoryError::Base64(e)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for SignatoryError {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        SignatoryError::Utf8(e)
+    }
+}
+
+impl From<serde_json::Error> for SignatoryError {
+    fn from(e: serde_json::Error) -> Self {
+        SignatoryError::Json(e)
+    }
+}
@@ -1,35 +1,88 @@
 use std::collections::HashMap;
-use std::error::Error;
 use base64::Engine;
 use serde_json::Value;
 use base64::engine::general_purpose; // Using the general-purpose base64 encoding engine
 use md5;
 use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use rsa::pkcs1v15::{SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::signature::{RandomizedSigner, SignatureEncoding, Verifier as RsaVerifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+
+mod algorithm;
+mod canonical;
+mod error;
+mod http;
+mod verify;
+pub use algorithm::Algorithm;
+pub use canonical::canonicalize;
+pub use error::SignatoryError;
+pub use http::{HttpRequest, HttpSigner};
+pub use verify::{NonceStore, VerifyConfig, VerifyError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Compares two byte strings without early-exiting on the first mismatch, so
+/// that signature verification does not leak how many leading bytes matched
+/// through comparison timing. Still checks lengths up front, which is an
+/// unavoidable (and harmless) leak, since differing lengths can never be equal.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
 
 /// Struct responsible for signing operations.
 pub struct Signatory {
-    key: String, // Secret key used for generating signatures
+    key: String, // Secret key (or, for `RsaSha256`, a PEM-encoded private key)
+    algorithm: Algorithm,
 }
 
 impl Signatory {
-    /// Creates a new instance of the Signatory struct with the provided secret key.
+    /// Creates a new instance of the Signatory struct with the provided secret key,
+    /// signing with the original `Md5` algorithm.
     ///
     /// # Arguments
     ///
     /// * `key` - A `String` representing the secret key to be used in signing.
     pub fn new(key: String) -> Signatory {
-        Signatory { key }
+        Signatory {
+            key,
+            algorithm: Algorithm::Md5,
+        }
     }
 
-    /// Generates a signature from a given `HashMap<String, Value>`.
+    /// Creates a new instance of the Signatory struct with an explicit signing
+    /// algorithm. For `Algorithm::RsaSha256`, `key` must be a PEM-encoded RSA
+    /// private key rather than a shared secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A `String` representing the secret key (or PEM private key) to sign with.
+    /// * `algorithm` - The `Algorithm` to dispatch on when signing.
+    pub fn with_algorithm(key: String, algorithm: Algorithm) -> Signatory {
+        Signatory { key, algorithm }
+    }
+
+    /// Generates a signature from a given `HashMap<String, Value>`, dispatching
+    /// on `self.algorithm`.
+    ///
+    /// The parameters are first reduced to a [`canonicalize`]d byte string
+    /// (sorted keys, no whitespace, nested objects/arrays/numbers preserved)
+    /// rather than the old lossy `key=value&...` join, so the signature covers
+    /// every field instead of silently dropping non-string values.
     ///
-    /// Steps:
-    /// 1. Removes the `sign` field from `params` if it exists.
-    /// 2. Sorts the remaining keys in ascending order.
-    /// 3. Builds a query string from key-value pairs.
-    /// 4. Appends the secret key to the query string.
-    /// 5. Computes the MD5 hash of the string.
-    /// 6. Converts the hash to an uppercase hexadecimal string and returns it.
+    /// * `Md5` appends `&key=<secret>` to the canonical payload and hashes the result.
+    /// * `HmacSha256` MACs the canonical payload with the secret as key (no `&key=` suffix).
+    /// * `RsaSha256` signs the SHA-256 digest of the canonical payload with the PEM private key.
     ///
     /// # Arguments
     ///
@@ -37,44 +90,66 @@ impl Signatory {
     ///
     /// # Returns
     ///
-    /// Returns the generated signature as a `Result<String, Box<dyn Error>>`.
+    /// Returns the generated signature as a `Result<String, SignatoryError>`.
     pub fn gen_signature(
         &self,
         mut params: HashMap<String, Value>,
-    ) -> Result<String, Box<dyn Error>> {
+    ) -> Result<String, SignatoryError> {
         // Ensure `params` is not empty
         if params.is_empty() {
-            return Err("Params is empty".into());
+            return Err(SignatoryError::EmptyParams);
         }
 
-        // Remove the "sign" field if it exists
+        // Remove the "sign" field if it exists; it must not sign itself
         params.remove("sign");
 
-        // Collect and sort the keys of the HashMap
-        let mut keys: Vec<String> = params.keys().cloned().collect();
-        keys.sort();
-
-        // Build the query string by iterating over sorted keys and values
-        let payload: String = keys
-            .iter()
-            .filter_map(|key| {
-                // Convert each value to a string, skipping keys with non-string values
-                match params.get(key) {
-                    Some(value) => value.as_str().map(|v| format!("{}={}", key, v)),
-                    None => None,
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("&");
+        let payload = canonicalize(&params);
+        self.sign_bytes(payload.as_bytes())
+    }
 
-        // Append the secret key to the query string
-        let payload_with_key = format!("{}&key={}", payload, self.key);
+    /// Signs an arbitrary byte string under `self.algorithm`, without any
+    /// JSON canonicalization, returning the *raw* signature bytes with no
+    /// text encoding applied. Shared by [`Self::sign_bytes`] (which formats
+    /// the result as hex/base64 for the JSON param path) and
+    /// [`crate::http::HttpSigner`] (which base64-encodes it itself for the
+    /// `Signature` header, regardless of algorithm).
+    pub(crate) fn sign_raw(&self, payload: &[u8]) -> Result<Vec<u8>, SignatoryError> {
+        match self.algorithm {
+            Algorithm::Md5 => {
+                // Append the secret key to the payload
+                let mut payload_with_key = payload.to_vec();
+                payload_with_key.extend_from_slice(format!("&key={}", self.key).as_bytes());
 
-        // Compute the MD5 hash of the final payload
-        let digest = md5::compute(payload_with_key);
+                // Compute the MD5 hash of the final payload
+                Ok(md5::compute(payload_with_key).0.to_vec())
+            }
+            Algorithm::HmacSha256 => {
+                let mut mac = HmacSha256::new_from_slice(self.key.as_bytes())
+                    .map_err(|e| SignatoryError::Sign(format!("Invalid HMAC key: {}", e)))?;
+                mac.update(payload);
+                Ok(mac.finalize().into_bytes().to_vec())
+            }
+            Algorithm::RsaSha256 => {
+                let private_key = RsaPrivateKey::from_pkcs8_pem(&self.key)
+                    .map_err(|e| SignatoryError::Sign(format!("Invalid RSA private key: {}", e)))?;
+                let signing_key = SigningKey::<Sha256>::new(private_key);
+                let signature = signing_key.sign_with_rng(&mut rand::thread_rng(), payload);
+                Ok(signature.to_bytes().to_vec())
+            }
+        }
+    }
 
-        // Convert the hash to uppercase hexadecimal and return the result
-        Ok(format!("{:x}", digest).to_ascii_uppercase())
+    /// Signs an arbitrary byte string under `self.algorithm`, without any
+    /// JSON canonicalization, and formats the result the way the JSON param
+    /// path has always expected: uppercase hex for `Md5`/`HmacSha256`, base64
+    /// for `RsaSha256`. Shared by [`Self::gen_signature`] (which signs the
+    /// canonical param payload).
+    pub(crate) fn sign_bytes(&self, payload: &[u8]) -> Result<String, SignatoryError> {
+        let raw = self.sign_raw(payload)?;
+        Ok(match self.algorithm {
+            Algorithm::Md5 | Algorithm::HmacSha256 => hex::encode_upper(raw),
+            Algorithm::RsaSha256 => general_purpose::STANDARD.encode(raw),
+        })
     }
 
     /// Converts a `HashMap<String, Value>` into a Base64-encoded string.
@@ -91,14 +166,14 @@ impl Signatory {
     ///
     /// # Returns
     ///
-    /// Returns the Base64 encoded string as `Result<String, Box<dyn Error>>`.
+    /// Returns the Base64 encoded string as `Result<String, SignatoryError>`.
     pub fn to_base64_str(
         &self,
         mut params: HashMap<String, Value>,
-    ) -> Result<String, Box<dyn Error>> {
+    ) -> Result<String, SignatoryError> {
         // Check if `params` is empty
         if params.is_empty() {
-            return Err("Params is empty".into());
+            return Err(SignatoryError::EmptyParams);
         }
 
         // Insert current timestamp if it doesn't exist
@@ -109,13 +184,12 @@ impl Signatory {
 
         // Insert signature if it doesn't exist
         if !params.contains_key("sign") {
-            let sign = self.gen_signature(params.clone()).unwrap(); // Generate signature
+            let sign = self.gen_signature(params.clone())?; // Generate signature
             params.insert("sign".to_string(), Value::String(sign));
         }
 
         // Serialize `HashMap` to a JSON string
-        let body = serde_json::to_string(&params)
-            .map_err(|e| format!("Failed to serialize params to JSON: {}", e))?;
+        let body = serde_json::to_string(&params)?;
 
         // Encode the JSON string to Base64
         let encoded = general_purpose::STANDARD.encode(body);
@@ -136,21 +210,39 @@ impl Signatory {
     ///
     /// # Returns
     ///
-    /// Returns the decoded `HashMap<String, Value>` as `Result<HashMap<String, Value>, Box<dyn Error>>`.
+    /// Returns the decoded `HashMap<String, Value>` as `Result<HashMap<String, Value>, SignatoryError>`.
     pub fn decrypt_base64_str(
         &self,
         params: String,
-    ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+    ) -> Result<HashMap<String, Value>, SignatoryError> {
         // Base64 decode the input string
-        let bytes = general_purpose::STANDARD.decode(&params).unwrap();
+        let bytes = general_purpose::STANDARD.decode(&params)?;
 
         // Convert the decoded bytes into a UTF-8 string
-        let body = String::from_utf8(bytes).unwrap();
+        let body = String::from_utf8(bytes)?;
 
         // Deserialize the string into a HashMap
-        let result = serde_json::from_str(&body).unwrap();
+        let result = serde_json::from_str(&body)?;
         Ok(result)
     }
+}
+
+/// Struct responsible for verifying signatures produced by [`Signatory`].
+///
+/// Kept separate from `Signatory` because `Algorithm::RsaSha256` verifies with
+/// the *public* key, not the private key used to sign; `Md5` and `HmacSha256`
+/// simply hold the same shared secret on both sides.
+pub struct Verifier {
+    key: String, // Secret key (or, for `RsaSha256`, a PEM-encoded public key)
+    algorithm: Algorithm,
+}
+
+impl Verifier {
+    /// Creates a new `Verifier` for the given algorithm. For `Algorithm::RsaSha256`,
+    /// `key` must be a PEM-encoded RSA public key rather than a shared secret.
+    pub fn new(key: String, algorithm: Algorithm) -> Verifier {
+        Verifier { key, algorithm }
+    }
 
     /// Verifies the integrity of the provided signature.
     ///
@@ -158,6 +250,10 @@ impl Signatory {
     /// 1. Regenerates the signature based on the `params`.
     /// 2. Compares the regenerated signature with the provided `sign`.
     ///
+    /// For `Algorithm::RsaSha256`, regeneration instead re-verifies the RSA
+    /// signature against the public key, since a `Verifier` only holds the
+    /// public half and so cannot regenerate the signature itself.
+    ///
     /// # Arguments
     ///
     /// * `params` - A `HashMap<String, Value>` representing the parameters to verify.
@@ -167,12 +263,148 @@ impl Signatory {
     ///
     /// Returns `true` if the signature matches, otherwise `false`.
     pub fn check_signature(&self, params: HashMap<String, Value>, sign: String) -> bool {
-        let value = self.gen_signature(params);
-        if value.is_err() {
-            return false;
+        self.signature_matches(params, &sign)
+    }
+
+    /// Verifies the signature and, additionally, the `timestamp` field injected
+    /// by [`Signatory::to_base64_str`] (and optionally a caller-supplied `nonce`
+    /// field), so that a captured request cannot be replayed indefinitely.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters to verify, including `timestamp` and, if
+    ///   `config.require_nonce` is set, `nonce`.
+    /// * `sign` - The signature to verify.
+    /// * `config` - Freshness/replay policy to enforce.
+    /// * `nonce_store` - Consulted (and updated) when `config.require_nonce` is set.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the signature and freshness checks all pass, otherwise the
+    /// first `VerifyError` encountered.
+    pub fn check_signature_with_config(
+        &self,
+        params: HashMap<String, Value>,
+        sign: String,
+        config: VerifyConfig,
+        nonce_store: Option<&dyn NonceStore>,
+    ) -> Result<(), VerifyError> {
+        let timestamp: i64 = params
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .ok_or(VerifyError::MissingField("timestamp"))?
+            .parse()
+            .map_err(|_| VerifyError::MissingField("timestamp"))?;
+
+        let now = Utc::now().timestamp();
+
+        if now - timestamp > config.max_age.as_secs() as i64 {
+            return Err(VerifyError::Expired);
+        }
+        if timestamp - now > config.reject_future.as_secs() as i64 {
+            return Err(VerifyError::ClockSkew);
+        }
+
+        let nonce = if config.require_nonce {
+            let nonce = params
+                .get("nonce")
+                .and_then(|v| v.as_str())
+                .ok_or(VerifyError::MissingField("nonce"))?
+                .to_string();
+            let store = nonce_store.ok_or(VerifyError::MissingField("nonce"))?;
+            if store.seen(&nonce) {
+                return Err(VerifyError::ReplayedNonce);
+            }
+            Some((store, nonce))
+        } else {
+            None
+        };
+
+        if !self.signature_matches(params, &sign) {
+            return Err(VerifyError::SignatureMismatch);
+        }
+
+        // Only burn the nonce once the signature is confirmed valid, so a
+        // forged request can't pre-mark a nonce as seen and deny the
+        // legitimate request that uses it.
+        if let Some((store, nonce)) = nonce {
+            store.remember(&nonce, timestamp + config.max_age.as_secs() as i64);
+        }
+
+        Ok(())
+    }
+
+    /// Regenerates (or, for RSA, re-verifies) the signature and compares it
+    /// against `sign`.
+    fn signature_matches(&self, mut params: HashMap<String, Value>, sign: &str) -> bool {
+        params.remove("sign");
+        let payload = canonicalize(&params);
+        self.verify_bytes(payload.as_bytes(), sign)
+    }
+
+    /// Verifies `sign` over an arbitrary byte string under `self.algorithm`,
+    /// without any JSON canonicalization, where `sign` is formatted the way
+    /// the JSON param path has always expected (uppercase hex for
+    /// `Md5`/`HmacSha256`, base64 for `RsaSha256`). Shared by
+    /// [`Self::signature_matches`], which verifies the canonical param payload.
+    pub(crate) fn verify_bytes(&self, payload: &[u8], sign: &str) -> bool {
+        match self.algorithm {
+            Algorithm::RsaSha256 => {
+                let signature_bytes = match general_purpose::STANDARD.decode(sign) {
+                    Ok(b) => b,
+                    Err(_) => return false,
+                };
+                self.check_rsa_signature(payload, &signature_bytes)
+            }
+            Algorithm::Md5 | Algorithm::HmacSha256 => {
+                let signatory = Signatory {
+                    key: self.key.clone(),
+                    algorithm: self.algorithm,
+                };
+                match signatory.sign_bytes(payload) {
+                    Ok(expected) => constant_time_eq(expected.as_bytes(), sign.as_bytes()),
+                    Err(_) => false,
+                }
+            }
         }
+    }
 
-        value.unwrap() == sign
+    /// Verifies a signature over an arbitrary byte string under
+    /// `self.algorithm`, without any JSON canonicalization and with `sign`
+    /// already decoded to raw bytes. Used by
+    /// [`crate::http::HttpSigner::verify_request`], which base64-decodes the
+    /// `Signature` header's `signature=` field itself (the header is always
+    /// base64 regardless of algorithm, unlike the JSON param path's
+    /// hex-for-symmetric-algorithms convention).
+    pub(crate) fn verify_raw(&self, payload: &[u8], sign: &[u8]) -> bool {
+        match self.algorithm {
+            Algorithm::RsaSha256 => self.check_rsa_signature(payload, sign),
+            Algorithm::Md5 | Algorithm::HmacSha256 => {
+                let signatory = Signatory {
+                    key: self.key.clone(),
+                    algorithm: self.algorithm,
+                };
+                match signatory.sign_raw(payload) {
+                    Ok(expected) => constant_time_eq(&expected, sign),
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+
+    /// Verifies an RSA-PKCS1v1.5 signature against this verifier's public key.
+    fn check_rsa_signature(&self, payload: &[u8], signature_bytes: &[u8]) -> bool {
+        let public_key = match RsaPublicKey::from_public_key_pem(&self.key) {
+            Ok(k) => k,
+            Err(_) => return false,
+        };
+        let signature = match rsa::pkcs1v15::Signature::try_from(signature_bytes) {
+            Ok(s) => s,
+            Err(_) => return false,
+        };
+
+        let verifying_key = VerifyingKey::<Sha256>::new(public_key);
+        verifying_key.verify(payload, &signature).is_ok()
     }
 }
 
@@ -198,8 +430,9 @@ mod tests {
         let sign = signatory.gen_signature(params.clone()).unwrap();
         println!("Generated sign: {}", sign);
 
-        // Manually provided expected signature (from the decoded JSON)
-        let expected_sign = "4D49FFFDE0DA4537160CFC258356277B";
+        // Manually provided expected signature, computed over the canonical
+        // JSON payload (sorted keys, no whitespace) plus the secret key
+        let expected_sign = "F8834B3D1BE660D268DFF7278D16F848";
 
         // Assert that the generated signature matches the expected one
         assert_eq!(sign, expected_sign, "The generated signature should match the expected signature");
@@ -216,7 +449,153 @@ mod tests {
         assert_eq!(params, decoded_params, "Decoded params should match the original params");
 
         // Check if signature is valid
-        let is_valid = signatory.check_signature(decoded_params.clone(), sign.clone());
+        let verifier = Verifier::new("ds069ed4223ac1660f".to_string(), Algorithm::Md5);
+        let is_valid = verifier.check_signature(decoded_params.clone(), sign.clone());
         assert!(is_valid, "Signature should be valid");
     }
+
+    #[test]
+    fn test_hmac_sha256_sign_and_verify_round_trip() {
+        let key = "hmac-secret".to_string();
+        let signatory = Signatory::with_algorithm(key.clone(), Algorithm::HmacSha256);
+
+        let mut params = HashMap::new();
+        params.insert("amount".to_string(), Value::from(42));
+        params.insert("currency".to_string(), Value::String("USD".to_string()));
+
+        let sign = signatory.gen_signature(params.clone()).unwrap();
+
+        let verifier = Verifier::new(key, Algorithm::HmacSha256);
+        assert!(verifier.check_signature(params.clone(), sign.clone()));
+
+        // A tampered field must not verify against the original signature
+        params.insert("amount".to_string(), Value::from(43));
+        assert!(!verifier.check_signature(params, sign));
+    }
+
+    #[test]
+    fn test_rsa_sha256_sign_and_verify_round_trip() {
+        use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+
+        let mut rng = rand::thread_rng();
+        let private_key = RsaPrivateKey::new(&mut rng, 2048).expect("failed to generate RSA key");
+        let public_key = private_key.to_public_key();
+
+        let private_pem = private_key
+            .to_pkcs8_pem(LineEnding::LF)
+            .unwrap()
+            .to_string();
+        let public_pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+
+        let signatory = Signatory::with_algorithm(private_pem, Algorithm::RsaSha256);
+
+        let mut params = HashMap::new();
+        params.insert("order_id".to_string(), Value::String("A100".to_string()));
+
+        let sign = signatory.gen_signature(params.clone()).unwrap();
+
+        let verifier = Verifier::new(public_pem, Algorithm::RsaSha256);
+        assert!(verifier.check_signature(params, sign));
+    }
+
+    struct TestNonceStore {
+        seen: std::cell::RefCell<std::collections::HashSet<String>>,
+    }
+
+    impl TestNonceStore {
+        fn new() -> Self {
+            TestNonceStore {
+                seen: std::cell::RefCell::new(std::collections::HashSet::new()),
+            }
+        }
+    }
+
+    impl NonceStore for TestNonceStore {
+        fn seen(&self, nonce: &str) -> bool {
+            self.seen.borrow().contains(nonce)
+        }
+
+        fn remember(&self, nonce: &str, _expiry: i64) {
+            self.seen.borrow_mut().insert(nonce.to_string());
+        }
+    }
+
+    fn config(max_age_secs: u64, reject_future_secs: u64, require_nonce: bool) -> VerifyConfig {
+        VerifyConfig {
+            max_age: std::time::Duration::from_secs(max_age_secs),
+            reject_future: std::time::Duration::from_secs(reject_future_secs),
+            require_nonce,
+        }
+    }
+
+    #[test]
+    fn test_check_signature_with_config_rejects_expired_timestamp() {
+        let key = "hmac-secret".to_string();
+        let signatory = Signatory::with_algorithm(key.clone(), Algorithm::HmacSha256);
+
+        let mut params = HashMap::new();
+        params.insert(
+            "timestamp".to_string(),
+            Value::String((Utc::now().timestamp() - 120).to_string()),
+        );
+        let sign = signatory.gen_signature(params.clone()).unwrap();
+
+        let verifier = Verifier::new(key, Algorithm::HmacSha256);
+        let result =
+            verifier.check_signature_with_config(params, sign, config(60, 60, false), None);
+        assert_eq!(result, Err(VerifyError::Expired));
+    }
+
+    #[test]
+    fn test_check_signature_with_config_rejects_clock_skew() {
+        let key = "hmac-secret".to_string();
+        let signatory = Signatory::with_algorithm(key.clone(), Algorithm::HmacSha256);
+
+        let mut params = HashMap::new();
+        params.insert(
+            "timestamp".to_string(),
+            Value::String((Utc::now().timestamp() + 120).to_string()),
+        );
+        let sign = signatory.gen_signature(params.clone()).unwrap();
+
+        let verifier = Verifier::new(key, Algorithm::HmacSha256);
+        let result =
+            verifier.check_signature_with_config(params, sign, config(60, 60, false), None);
+        assert_eq!(result, Err(VerifyError::ClockSkew));
+    }
+
+    #[test]
+    fn test_check_signature_with_config_rejects_replayed_nonce_but_not_legitimate_first_use() {
+        let key = "hmac-secret".to_string();
+        let signatory = Signatory::with_algorithm(key.clone(), Algorithm::HmacSha256);
+
+        let mut params = HashMap::new();
+        params.insert(
+            "timestamp".to_string(),
+            Value::String(Utc::now().timestamp().to_string()),
+        );
+        params.insert("nonce".to_string(), Value::String("abc123".to_string()));
+        let sign = signatory.gen_signature(params.clone()).unwrap();
+
+        let verifier = Verifier::new(key, Algorithm::HmacSha256);
+        let store = TestNonceStore::new();
+        let cfg = config(60, 60, true);
+
+        // A forged signature must not be able to burn the nonce ahead of the
+        // legitimate request (regression test for the pre-burn denial bug).
+        let forged = verifier.check_signature_with_config(
+            params.clone(),
+            "not-the-real-signature".to_string(),
+            cfg,
+            Some(&store),
+        );
+        assert_eq!(forged, Err(VerifyError::SignatureMismatch));
+
+        let first =
+            verifier.check_signature_with_config(params.clone(), sign.clone(), cfg, Some(&store));
+        assert_eq!(first, Ok(()));
+
+        let replay = verifier.check_signature_with_config(params, sign, cfg, Some(&store));
+        assert_eq!(replay, Err(VerifyError::ReplayedNonce));
+    }
 }
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Controls how strictly [`crate::Verifier::check_signature_with_config`]
+/// treats the `timestamp` field injected by
+/// [`crate::Signatory::to_base64_str`] and the caller-supplied `nonce` field.
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyConfig {
+    /// How far in the past a `timestamp` may be before the request is stale.
+    pub max_age: Duration,
+    /// How far in the future a `timestamp` may be before it looks clock-skewed.
+    pub reject_future: Duration,
+    /// Whether a `nonce` field is mandatory and must be checked against a `NonceStore`.
+    pub require_nonce: bool,
+}
+
+/// Caller-supplied storage for nonces already seen within the freshness
+/// window, used to reject replayed requests.
+pub trait NonceStore {
+    /// Returns `true` if `nonce` has already been remembered.
+    fn seen(&self, nonce: &str) -> bool;
+    /// Records `nonce` as seen until the given Unix epoch `expiry`.
+    fn remember(&self, nonce: &str, expiry: i64);
+}
+
+/// Structured failure modes for signature verification, so callers can
+/// distinguish "signature wrong" from "replay" from "expired" instead of a
+/// bare `bool`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The regenerated signature does not match the supplied one.
+    SignatureMismatch,
+    /// `now - timestamp` exceeded `VerifyConfig::max_age`.
+    Expired,
+    /// `timestamp` was more than `VerifyConfig::reject_future` ahead of `now`.
+    ClockSkew,
+    /// The `nonce` has already been used within the freshness window.
+    ReplayedNonce,
+    /// A required field (`timestamp`, or `nonce` when `require_nonce` is set) was absent,
+    /// `timestamp` was not a string holding a Unix epoch, or `require_nonce` is set
+    /// but no `NonceStore` was supplied.
+    MissingField(&'static str),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::SignatureMismatch => write!(f, "signature does not match"),
+            VerifyError::Expired => write!(f, "timestamp is too old"),
+            VerifyError::ClockSkew => write!(f, "timestamp is too far in the future"),
+            VerifyError::ReplayedNonce => write!(f, "nonce has already been used"),
+            VerifyError::MissingField(field) => write!(f, "missing required field `{}`", field),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}